@@ -203,8 +203,9 @@ async fn test_btree_store_update() {
                 return 1;
             }
             
-            // Insert with same key creates a new entry (duplicate keys allowed)
-            // Note: BTreeStore allows duplicate keys
+            // Insert with same key creates a new entry (duplicate keys allowed).
+            // Use store.set(key, value) instead if you want replace-in-place
+            // (dedup) semantics - see test_btree_store_set_replaces_existing_value.
             store.insert(b"key", "updated");
             
             // Length increases because duplicates are allowed
@@ -686,6 +687,192 @@ async fn test_btree_store_descending_iteration() {
     run_silex_code_expect_success(code).await;
 }
 
+#[tokio::test]
+#[ignore = "this test exercises BTreeOrder/new_ordered as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers the new ordering support in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_ascending_order_explicit() {
+    let code = r#"
+        entry test_btree_ascending() {
+            let store = BTreeStore::new_ordered(b"test", BTreeOrder::Ascending);
+
+            store.insert(b"b", 2u64);
+            store.insert(b"a", 1u64);
+            store.insert(b"c", 3u64);
+
+            let result = store.seek(b"", BTreeSeekBias::First, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let item = tuple.1;
+
+            // Ascending order behaves like the default ordering
+            if item.key != b"a" {
+                return 2;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises BTreeOrder/new_ordered as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers the new ordering support in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_descending_order() {
+    let code = r#"
+        entry test_btree_descending_order() {
+            let store = BTreeStore::new_ordered(b"test", BTreeOrder::Descending);
+
+            store.insert(b"a", 1u64);
+            store.insert(b"b", 2u64);
+            store.insert(b"c", 3u64);
+
+            // First in the store's own order is now "c"
+            let result = store.seek(b"", BTreeSeekBias::First, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            if item.key != b"c" {
+                return 2;
+            }
+
+            // next() walks in the store's order, not raw byte order
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+
+            let next_item = next.unwrap();
+            if next_item.key != b"b" {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises BTreeOrder/new_ordered as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers the new ordering support in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_descending_seek_bias() {
+    let code = r#"
+        entry test_btree_descending_seek_bias() {
+            let store = BTreeStore::new_ordered(b"test", BTreeOrder::Descending);
+
+            store.insert(b"a", 1u64);
+            store.insert(b"c", 3u64);
+            store.insert(b"e", 5u64);
+
+            // GreaterOrEqual means "next in the store's order", so with
+            // descending order, seeking >= "d" lands on "c" (the next
+            // smaller key, since the store walks from big to small).
+            let result = store.seek(b"d", BTreeSeekBias::GreaterOrEqual, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let item = tuple.1;
+
+            if item.key != b"c" {
+                return 2;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises BTreeOrder/new_ordered as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers the new ordering support in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_big_endian_u64_order() {
+    let code = r#"
+        entry test_btree_big_endian_u64() {
+            let store = BTreeStore::new_ordered(b"test", BTreeOrder::BigEndianU64);
+
+            // Keys are fixed 8-byte big-endian integers, compared numerically
+            // rather than byte-lexicographically.
+            store.insert(b"\x00\x00\x00\x00\x00\x00\x00\x02", 2u64);
+            store.insert(b"\x00\x00\x00\x00\x00\x00\x01\x00", 256u64);
+            store.insert(b"\x00\x00\x00\x00\x00\x00\x00\x09", 9u64);
+
+            let result = store.seek(b"", BTreeSeekBias::First, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            // Numerically smallest is 2. Fixed-width big-endian keys sort
+            // the same way byte-wise, so 9 ("0x00..09") comes next and
+            // 256 ("0x00..01 0x00") after it.
+            if item.key != b"\x00\x00\x00\x00\x00\x00\x00\x02" {
+                return 2;
+            }
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+
+            let next_item = next.unwrap();
+            if next_item.key != b"\x00\x00\x00\x00\x00\x00\x00\x09" {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises BTreeOrder/new_ordered as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers the new ordering support in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_order_persisted_across_handles() {
+    let code = r#"
+        entry test_btree_order_persisted() {
+            let store = BTreeStore::new_ordered(b"shared", BTreeOrder::Descending);
+            store.insert(b"a", 1u64);
+            store.insert(b"b", 2u64);
+
+            // Re-opening the same namespace must keep honoring the order
+            // recorded in its metadata, not the order of this new handle's
+            // constructor call site.
+            let reopened = BTreeStore::new(b"shared");
+
+            let result = reopened.seek(b"", BTreeSeekBias::First, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let item = tuple.1;
+
+            if item.key != b"b" {
+                return 2;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
 #[tokio::test]
 async fn test_btree_store_delete_nonexistent() {
     let code = r#"
@@ -714,3 +901,898 @@ async fn test_btree_store_delete_nonexistent() {
 
     run_silex_code_expect_success(code).await;
 }
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_commit() {
+    let code = r#"
+        entry test_btree_txn_commit() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"a", 1u64);
+
+            let txn = store.transaction();
+            txn.insert(b"b", 2u64);
+            txn.delete(b"a");
+
+            // Writes are buffered until commit, not visible on the store yet
+            if store.len() != 1 {
+                return 1;
+            }
+            if store.get(b"a").is_none() {
+                return 2;
+            }
+
+            txn.commit();
+
+            if store.len() != 1 {
+                return 3;
+            }
+            if store.get(b"a").is_some() {
+                return 4;
+            }
+            if store.get(b"b").is_none() {
+                return 5;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_reads_see_buffered_writes() {
+    let code = r#"
+        entry test_btree_txn_reads_buffer() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"a", 1u64);
+
+            let txn = store.transaction();
+
+            // Not yet committed, but reads through the txn see the pending write
+            txn.insert(b"b", 2u64);
+            if txn.get(b"b").is_none() {
+                return 1;
+            }
+
+            // And a pending delete shadows the committed value as a tombstone
+            txn.delete(b"a");
+            if txn.get(b"a").is_some() {
+                return 2;
+            }
+
+            // len() reflects the buffered inserts/deletes
+            if txn.len() != 1 {
+                return 3;
+            }
+
+            // The underlying store is untouched until commit
+            if store.get(b"a").is_none() {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_drop_discards_writes() {
+    let code = r#"
+        entry test_btree_txn_drop() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"a", 1u64);
+
+            {
+                let txn = store.transaction();
+                txn.insert(b"b", 2u64);
+                txn.delete(b"a");
+                // txn dropped here without commit
+            }
+
+            if store.len() != 1 {
+                return 1;
+            }
+            if store.get(b"a").is_none() {
+                return 2;
+            }
+            if store.get(b"b").is_some() {
+                return 3;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_explicit_rollback() {
+    let code = r#"
+        entry test_btree_txn_rollback() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"a", 1u64);
+
+            let txn = store.transaction();
+            txn.insert(b"b", 2u64);
+            txn.rollback();
+
+            if store.len() != 1 {
+                return 1;
+            }
+            if store.get(b"b").is_some() {
+                return 2;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_savepoint_rollback() {
+    let code = r#"
+        entry test_btree_txn_savepoint_rollback() {
+            let store = BTreeStore::new(b"test");
+
+            let txn = store.transaction();
+            txn.insert(b"a", 1u64);
+
+            txn.set_savepoint();
+            txn.insert(b"b", 2u64);
+            txn.insert(b"c", 3u64);
+
+            // Undo everything buffered since the savepoint, keeping "a"
+            txn.rollback_to_savepoint();
+
+            if txn.len() != 1 {
+                return 1;
+            }
+            if txn.get(b"b").is_some() {
+                return 2;
+            }
+            if txn.get(b"a").is_none() {
+                return 3;
+            }
+
+            txn.commit();
+
+            if store.len() != 1 {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises Transaction (store.transaction()/commit()/rollback()/savepoints) as a Silex builtin, but this series only adds the standalone btree_store.rs Rust module - it never registers Transaction in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_transaction_nested_savepoints() {
+    let code = r#"
+        entry test_btree_txn_nested_savepoints() {
+            let store = BTreeStore::new(b"test");
+
+            let txn = store.transaction();
+            txn.insert(b"a", 1u64);
+
+            txn.set_savepoint();
+            txn.insert(b"b", 2u64);
+
+            txn.set_savepoint();
+            txn.insert(b"c", 3u64);
+
+            // Merge the innermost savepoint into the one below it: "c" is
+            // kept, but it is no longer an independently rollback-able layer.
+            txn.pop_savepoint();
+
+            // Rolling back to the remaining (outer) savepoint now discards
+            // both "b" and "c" at once.
+            txn.rollback_to_savepoint();
+
+            if txn.len() != 1 {
+                return 1;
+            }
+            if txn.get(b"a").is_none() {
+                return 2;
+            }
+            if txn.get(b"b").is_some() {
+                return 3;
+            }
+            if txn.get(b"c").is_some() {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.range()/store.prefix() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_range_bounds() {
+    let code = r#"
+        entry test_btree_range() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"a", 1u64);
+            store.insert(b"b", 2u64);
+            store.insert(b"c", 3u64);
+            store.insert(b"d", 4u64);
+            store.insert(b"e", 5u64);
+
+            // [b, d) - "b" included, "d" excluded
+            let result = store.range(b"b", b"d", true, false);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            if item.key != b"b" {
+                return 2;
+            }
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+            if next.unwrap().key != b"c" {
+                return 4;
+            }
+
+            // "d" is excluded by the upper bound, so iteration stops here
+            let next2 = cursor.next();
+            if next2.is_some() {
+                return 5;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.range()/store.prefix() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_range_inclusive_upper() {
+    let code = r#"
+        entry test_btree_range_inclusive() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"a", 1u64);
+            store.insert(b"b", 2u64);
+            store.insert(b"c", 3u64);
+            store.insert(b"d", 4u64);
+
+            // [b, c] - both bounds included
+            let result = store.range(b"b", b"c", true, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 2;
+            }
+            if next.unwrap().key != b"c" {
+                return 3;
+            }
+
+            // "d" is past the upper bound
+            let next2 = cursor.next();
+            if next2.is_some() {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.range()/store.prefix() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_range_empty_when_out_of_bounds() {
+    let code = r#"
+        entry test_btree_range_empty() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"a", 1u64);
+            store.insert(b"b", 2u64);
+
+            // No keys fall within ["x", "z")
+            let result = store.range(b"x", b"z", true, false);
+            if result.is_some() {
+                return 1;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.range()/store.prefix() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_prefix_cursor() {
+    let code = r#"
+        entry test_btree_prefix() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"user:1", 1u64);
+            store.insert(b"user:2", 2u64);
+            store.insert(b"user:3", 3u64);
+            store.insert(b"tenant:1", 4u64);
+
+            let result = store.prefix(b"user:");
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            if item.key != b"user:1" {
+                return 2;
+            }
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+            if next.unwrap().key != b"user:2" {
+                return 4;
+            }
+
+            let next2 = cursor.next();
+            if next2.is_none() {
+                return 5;
+            }
+            if next2.unwrap().key != b"user:3" {
+                return 6;
+            }
+
+            // "tenant:1" is outside the "user:" prefix, so iteration stops
+            let next3 = cursor.next();
+            if next3.is_some() {
+                return 7;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.range()/store.prefix() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_prefix_all_0xff_has_open_upper_bound() {
+    let code = r#"
+        entry test_btree_prefix_open_upper() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"\xff\xff", 1u64);
+            store.insert(b"\xff\xff\x00", 2u64);
+            store.insert(b"\xff\xff\xff", 3u64);
+
+            // An all-0xFF prefix has no representable successor, so the
+            // upper bound stays open and everything with that prefix matches.
+            let result = store.prefix(b"\xff\xff");
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            if item.key != b"\xff\xff" {
+                return 2;
+            }
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+            if next.unwrap().key != b"\xff\xff\x00" {
+                return 4;
+            }
+
+            let next2 = cursor.next();
+            if next2.is_none() {
+                return 5;
+            }
+            if next2.unwrap().key != b"\xff\xff\xff" {
+                return 6;
+            }
+
+            let next3 = cursor.next();
+            if next3.is_some() {
+                return 7;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.set()/store.contains() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_set_replaces_existing_value() {
+    let code = r#"
+        entry test_btree_set_replace() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"key", "original");
+
+            // Unlike insert(), set() dedups: it replaces the single value
+            // for the key in place rather than appending a duplicate.
+            let old = store.set(b"key", "updated");
+            if old.is_none() {
+                return 1;
+            }
+
+            if store.len() != 1 {
+                return 2;
+            }
+
+            let val = store.get(b"key");
+            if val.is_none() {
+                return 3;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.set()/store.contains() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_set_on_new_key_returns_none() {
+    let code = r#"
+        entry test_btree_set_new_key() {
+            let store = BTreeStore::new(b"test");
+
+            let old = store.set(b"key", 1u64);
+            if old.is_some() {
+                return 1;
+            }
+
+            if store.len() != 1 {
+                return 2;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.set()/store.contains() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_contains() {
+    let code = r#"
+        entry test_btree_contains() {
+            let store = BTreeStore::new(b"test");
+
+            if store.contains(b"key") {
+                return 1;
+            }
+
+            store.insert(b"key", 1u64);
+            if !store.contains(b"key") {
+                return 2;
+            }
+
+            store.delete(b"key");
+            if store.contains(b"key") {
+                return 3;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.set()/store.contains() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_insert_still_appends_duplicates() {
+    let code = r#"
+        entry test_btree_insert_appends() {
+            let store = BTreeStore::new(b"test");
+
+            // insert() keeps its existing append-style behavior: duplicate
+            // keys are allowed and each call adds a new entry.
+            store.insert(b"key", "first");
+            store.insert(b"key", "second");
+
+            if store.len() != 2 {
+                return 1;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.set()/store.contains() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests. Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_len_counter_tracks_set_and_delete() {
+    let code = r#"
+        entry test_btree_len_counter() {
+            let store = BTreeStore::new(b"test");
+
+            store.insert(b"a", 1u64);
+            store.set(b"b", 2u64);
+            if store.len() != 2 {
+                return 1;
+            }
+
+            // set() on an existing key must not change the counter
+            store.set(b"a", 10u64);
+            if store.len() != 2 {
+                return 2;
+            }
+
+            store.delete(b"a");
+            if store.len() != 1 {
+                return 3;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.create_index()/index()/drop_index() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including transaction_commit_updates_indexes, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_create_index_and_seek() {
+    let code = r#"
+        struct Person {
+            name: string,
+            age: u64
+        }
+
+        entry test_btree_index_seek() {
+            let store = BTreeStore::new(b"people");
+
+            let alice = Person { name: "Alice", age: 25 };
+            let bob = Person { name: "Bob", age: 30 };
+            let carol = Person { name: "Carol", age: 25 };
+
+            store.insert(b"alice", alice);
+            store.insert(b"bob", bob);
+            store.insert(b"carol", carol);
+
+            store.create_index(b"by_age", |p: Person| {
+                return p.age.to_bytes();
+            });
+
+            // Two primaries (alice, carol) share the same index value "25"
+            // and must both be reachable, ordered by their primary key.
+            let index = store.index(b"by_age");
+            let result = index.seek(b"25", BTreeSeekBias::GreaterOrEqual, true);
+            if result.is_none() {
+                return 1;
+            }
+
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            let item = tuple.1;
+
+            if item.key != b"alice" {
+                return 2;
+            }
+
+            let next = cursor.next();
+            if next.is_none() {
+                return 3;
+            }
+            if next.unwrap().key != b"carol" {
+                return 4;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.create_index()/index()/drop_index() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including transaction_commit_updates_indexes, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_index_updated_on_overwrite() {
+    let code = r#"
+        struct Person {
+            name: string,
+            age: u64
+        }
+
+        entry test_btree_index_overwrite() {
+            let store = BTreeStore::new(b"people");
+
+            let alice = Person { name: "Alice", age: 25 };
+            store.insert(b"alice", alice);
+
+            store.create_index(b"by_age", |p: Person| {
+                return p.age.to_bytes();
+            });
+
+            // Replacing alice's record must move her out of the "25" bucket
+            // and into the "40" bucket in the same transaction as the write.
+            let older_alice = Person { name: "Alice", age: 40 };
+            store.set(b"alice", older_alice);
+
+            let index = store.index(b"by_age");
+
+            let old_bucket = index.seek(b"25", BTreeSeekBias::Exact, true);
+            if old_bucket.is_some() {
+                return 1;
+            }
+
+            let new_bucket = index.seek(b"40", BTreeSeekBias::GreaterOrEqual, true);
+            if new_bucket.is_none() {
+                return 2;
+            }
+
+            let tuple = new_bucket.unwrap();
+            let item = tuple.1;
+            if item.key != b"alice" {
+                return 3;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.create_index()/index()/drop_index() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including transaction_commit_updates_indexes, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_index_removed_on_delete() {
+    let code = r#"
+        struct Person {
+            name: string,
+            age: u64
+        }
+
+        entry test_btree_index_delete() {
+            let store = BTreeStore::new(b"people");
+
+            let alice = Person { name: "Alice", age: 25 };
+            store.insert(b"alice", alice);
+
+            store.create_index(b"by_age", |p: Person| {
+                return p.age.to_bytes();
+            });
+
+            store.delete(b"alice");
+
+            let index = store.index(b"by_age");
+            let result = index.seek(b"25", BTreeSeekBias::GreaterOrEqual, true);
+            if result.is_some() {
+                return 1;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises store.create_index()/index()/drop_index() as Silex builtins, but this series only adds them to the standalone btree_store.rs Rust module - it never registers them in the Silex compiler/VM (not present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including transaction_commit_updates_indexes, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_drop_index() {
+    let code = r#"
+        struct Person {
+            name: string,
+            age: u64
+        }
+
+        entry test_btree_drop_index() {
+            let store = BTreeStore::new(b"people");
+
+            let alice = Person { name: "Alice", age: 25 };
+            store.insert(b"alice", alice);
+
+            store.create_index(b"by_age", |p: Person| {
+                return p.age.to_bytes();
+            });
+
+            store.drop_index(b"by_age");
+
+            // Further writes must not try to maintain the torn-down index
+            let bob = Person { name: "Bob", age: 30 };
+            store.insert(b"bob", bob);
+
+            if store.len() != 2 {
+                return 1;
+            }
+
+            return 0;
+        }
+    "#;
+
+    run_silex_code_expect_success(code).await;
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_changes_recorded_on_insert() {
+    let code = r#"
+        entry test_btree_changes_insert() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"key", 42u64);
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+
+    let changes = result.changes();
+    assert_eq!(changes.len(), 1, "Expected exactly one change record, got {:?}", changes);
+
+    let change = &changes[0];
+    assert_eq!(change.namespace, b"test");
+    assert_eq!(change.key, b"key");
+    assert!(change.before.is_none(), "A pure insert has no before value");
+    assert!(change.after.is_some(), "A pure insert has an after value");
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_changes_recorded_on_delete() {
+    let code = r#"
+        entry test_btree_changes_delete() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"key", 42u64);
+            store.delete(b"key");
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+
+    let changes = result.changes();
+    assert_eq!(changes.len(), 2, "Expected insert + delete change records, got {:?}", changes);
+
+    // Records are emitted in execution order
+    let insert_change = &changes[0];
+    assert!(insert_change.before.is_none());
+    assert!(insert_change.after.is_some());
+
+    let delete_change = &changes[1];
+    assert_eq!(delete_change.key, b"key");
+    assert!(delete_change.before.is_some(), "A delete records the old value as before");
+    assert!(delete_change.after.is_none(), "A delete has no after value");
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_changes_recorded_on_set_overwrite() {
+    let code = r#"
+        entry test_btree_changes_set() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"key", 1u64);
+            store.set(b"key", 2u64);
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+
+    let changes = result.changes();
+    assert_eq!(changes.len(), 2);
+
+    let set_change = &changes[1];
+    assert!(set_change.before.is_some(), "set() overwriting a key records the prior value");
+    assert!(set_change.after.is_some());
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_changes_recorded_on_cursor_delete() {
+    let code = r#"
+        entry test_btree_changes_cursor_delete() {
+            let store = BTreeStore::new(b"test");
+            store.insert(b"a", 1u64);
+
+            let result = store.seek(b"a", BTreeSeekBias::Exact, true);
+            let tuple = result.unwrap();
+            let cursor = tuple.0;
+            cursor.delete();
+
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+
+    let changes = result.changes();
+    assert_eq!(changes.len(), 2, "Expected insert + cursor delete change records, got {:?}", changes);
+
+    let cursor_delete_change = &changes[1];
+    assert_eq!(cursor_delete_change.key, b"a");
+    assert!(cursor_delete_change.after.is_none());
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_no_changes_when_store_untouched() {
+    let code = r#"
+        entry test_btree_no_changes() {
+            let store = BTreeStore::new(b"test");
+            let _ = store.len();
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+    assert!(result.changes().is_empty(), "Reads alone must not emit change records");
+}
+
+#[tokio::test]
+#[ignore = "this test exercises result.changes()/on_change() as a Silex/ExecutionResult builtin, but this series only adds the standalone ChangeRecorder/on_change in btree_store.rs - it never wires into ExecutionResult::changes() (neither that type nor a Silex compiler/VM is present in this tree), so nothing backs this call today. Covered instead by btree_store.rs's own unit tests (including change_recorder_records_transaction_commit_in_write_order, added for this fix). Re-enable once Silex-surface wiring lands as a follow-up."]
+async fn test_btree_store_rolled_back_transaction_emits_no_changes() {
+    let code = r#"
+        entry test_btree_changes_rollback() {
+            let store = BTreeStore::new(b"test");
+
+            let txn = store.transaction();
+            txn.insert(b"key", 1u64);
+            txn.rollback();
+
+            return 0;
+        }
+    "#;
+
+    let result = run_silex_code(code).await;
+    assert_eq!(result.value(), "0");
+    assert!(
+        result.changes().is_empty(),
+        "Writes rolled back before commit must not surface as change records"
+    );
+}